@@ -1,14 +1,21 @@
 #![allow(unused)]
+use bip39::Mnemonic;
+use bitcoin::bip32::{DerivationPath, Xpriv};
 use bitcoin::hex::DisplayHex;
+use bitcoin::secp256k1::Secp256k1;
 use bitcoincore_rpc::bitcoin::{Address, Amount, Network};
+use bitcoincore_rpc::json::AddressType;
 use bitcoincore_rpc::{Auth, Client, RpcApi};
-use serde::Deserialize;
+use clap::{Parser, Subcommand, ValueEnum};
+use prettytable::{row, Table};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::env;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-// Node access params
+// Node access params (fallback defaults when the corresponding env var is unset)
 const RPC_URL: &str = "http://127.0.0.1:18443"; // Default regtest RPC port
 const RPC_USER: &str = "alice";
 const RPC_PASS: &str = "password";
@@ -18,9 +25,198 @@ const MINER_WALLET_NAME: &str = "Miner";
 const TRADER_WALLET_NAME: &str = "Trader";
 const MINER_ADDRESS_LABEL: &str = "Mining Reward";
 const TRADER_ADDRESS_LABEL: &str = "Received";
-const TRANSACTION_AMOUNT_TO_SEND: f64 = 20.0;
 
-fn verify_wallet(rpc: &Client, wallet_name: &str) -> bitcoincore_rpc::Result<Client> {
+/// Regtest wallet experimentation tool driven by Bitcoin Core RPC.
+#[derive(Parser)]
+#[command(name = "rust-capstone-project", about, version)]
+struct Cli {
+    /// Print the node's full blockchain info before running the command.
+    #[arg(long, short, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Address script type to request when generating a new address.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum AddressKind {
+    /// P2PKH (`1...`).
+    Legacy,
+    /// P2SH-wrapped segwit (`3...`).
+    P2shSegwit,
+    /// Native segwit v0, P2WPKH (`bc1q.../bcrt1q...`).
+    Bech32,
+    /// Segwit v1 taproot, P2TR (`bc1p.../bcrt1p...`).
+    Bech32m,
+}
+
+impl AddressKind {
+    fn to_rpc(self) -> AddressType {
+        match self {
+            AddressKind::Legacy => AddressType::Legacy,
+            AddressKind::P2shSegwit => AddressType::P2shSegwit,
+            AddressKind::Bech32 => AddressType::Bech32,
+            AddressKind::Bech32m => AddressType::Bech32m,
+        }
+    }
+}
+
+/// How to render a transaction report.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    /// A `serde_json` object with named fields (machine-parseable).
+    Json,
+    /// A human-readable table.
+    Table,
+    /// The legacy ten-line `../out.txt` dump, for backward compatibility.
+    Legacy,
+}
+
+/// Named fields of a transaction report, shared by every output format.
+/// The two address fields appear in the table and legacy formats only; the
+/// JSON object leaves them out.
+#[derive(Serialize)]
+struct TxReportData {
+    txid: String,
+    miner_input_address: String,
+    miner_input_amount: f64,
+    trader_output: f64,
+    #[serde(skip)]
+    trader_output_address: String,
+    miner_change: f64,
+    #[serde(skip)]
+    miner_change_address: String,
+    fee: f64,
+    block_height: u64,
+    block_hash: String,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create (if needed) and load a wallet, printing a fresh receive address.
+    NewWallet {
+        /// Name of the wallet to create or load.
+        wallet: String,
+        /// Script type of the receive address (defaults to the node's setting).
+        #[arg(long, value_enum)]
+        address_type: Option<AddressKind>,
+    },
+    /// Create a descriptor wallet derived from a BIP39 mnemonic.
+    NewDescriptorWallet {
+        /// Name of the descriptor wallet to create and load.
+        wallet: String,
+        /// Mnemonic to restore from; a fresh 12-word one is generated if omitted.
+        #[arg(long)]
+        mnemonic: Option<String>,
+        /// Derive taproot (BIP86 `tr`) descriptors instead of segwit (BIP84 `wpkh`).
+        #[arg(long)]
+        taproot: bool,
+    },
+    /// Mine a number of blocks, paying the reward to the given wallet.
+    Mine {
+        /// Wallet that receives the mining reward.
+        wallet: String,
+        /// Number of blocks to generate.
+        blocks: u64,
+        /// Script type of the reward address (defaults to the node's setting).
+        #[arg(long, value_enum)]
+        address_type: Option<AddressKind>,
+    },
+    /// Send an amount (in BTC) from a wallet to an address.
+    SendToAddress {
+        /// Wallet to spend from.
+        wallet: String,
+        /// Destination address.
+        address: String,
+        /// Amount to send, in BTC.
+        amount: f64,
+        /// Explicit fee rate in sat/vB (defaults to the node's estimate).
+        #[arg(long)]
+        fee_rate: Option<f64>,
+        /// Signal BIP125 replace-by-fee so the transaction can be bumped later.
+        #[arg(long)]
+        replaceable: bool,
+    },
+    /// Replace a stuck mempool transaction with a higher-fee one (BIP125 RBF).
+    BumpFee {
+        /// Wallet that owns the transaction.
+        wallet: String,
+        /// Transaction id to bump.
+        txid: String,
+        /// Target fee rate for the replacement, in sat/vB.
+        #[arg(long)]
+        fee_rate: Option<f64>,
+    },
+    /// Print the spendable balance of a wallet.
+    Balance {
+        /// Wallet to query.
+        wallet: String,
+    },
+    /// Produce a detailed report for a confirmed transaction.
+    ///
+    /// Must be run against the *spending* wallet: outputs it owns are reported
+    /// as `miner_change` and the remainder as `trader_output`. Pointed at the
+    /// receiving wallet the two would be swapped.
+    TxReport {
+        /// Spending wallet that owns the transaction.
+        wallet: String,
+        /// Transaction id to report on.
+        txid: String,
+        /// Output format for the report.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+/// RPC connection and wallet configuration.
+///
+/// Values are resolved from the `RPC_URL`, `RPC_USER` and `RPC_PASS`
+/// environment variables (mirroring the bdk `wallet_rpc` example), falling
+/// back to the compiled-in constants. Setting `RPC_COOKIE_FILE` switches to
+/// cookie-file authentication so the tool can talk to a node without
+/// embedding credentials.
+struct Config {
+    rpc_url: String,
+    rpc_user: String,
+    rpc_pass: String,
+    cookie_file: Option<PathBuf>,
+}
+
+impl Config {
+    fn from_env() -> Self {
+        Self {
+            rpc_url: env::var("RPC_URL").unwrap_or_else(|_| RPC_URL.to_owned()),
+            rpc_user: env::var("RPC_USER").unwrap_or_else(|_| RPC_USER.to_owned()),
+            rpc_pass: env::var("RPC_PASS").unwrap_or_else(|_| RPC_PASS.to_owned()),
+            cookie_file: env::var("RPC_COOKIE_FILE").ok().map(PathBuf::from),
+        }
+    }
+
+    /// Authentication scheme shared by every client this config opens.
+    fn auth(&self) -> Auth {
+        match &self.cookie_file {
+            Some(path) => Auth::CookieFile(path.clone()),
+            None => Auth::UserPass(self.rpc_user.clone(), self.rpc_pass.clone()),
+        }
+    }
+
+    /// Base (wallet-less) client used for chain-wide queries.
+    fn client(&self) -> bitcoincore_rpc::Result<Client> {
+        Client::new(&self.rpc_url, self.auth())
+    }
+
+    /// Client scoped to a particular wallet endpoint.
+    fn wallet_client(&self, wallet_name: &str) -> bitcoincore_rpc::Result<Client> {
+        let wallet_url = format!("{}/wallet/{}", self.rpc_url, wallet_name);
+        Client::new(&wallet_url, self.auth())
+    }
+}
+
+fn verify_wallet(
+    config: &Config,
+    rpc: &Client,
+    wallet_name: &str,
+) -> bitcoincore_rpc::Result<Client> {
     let wallet_names_in_dir = rpc.list_wallet_dir()?;
     let wallet_exists = wallet_names_in_dir.iter().any(|w| w == wallet_name);
 
@@ -39,82 +235,301 @@ fn verify_wallet(rpc: &Client, wallet_name: &str) -> bitcoincore_rpc::Result<Cli
         println!("Wallet '{wallet_name}' is already loaded");
     }
 
-    let wallet_url = format!("{RPC_URL}/wallet/{wallet_name}");
-    let wallet_client = Client::new(
-        &wallet_url,
-        Auth::UserPass(RPC_USER.to_owned(), RPC_PASS.to_owned()),
-    )?;
+    let wallet_client = config.wallet_client(wallet_name)?;
 
     Ok(wallet_client)
 }
 
-fn main() -> bitcoincore_rpc::Result<()> {
-    // Connect to Bitcoin Core RPC
-    let rpc = Client::new(
-        RPC_URL,
-        Auth::UserPass(RPC_USER.to_owned(), RPC_PASS.to_owned()),
+/// Create a descriptor wallet derived from a BIP39 mnemonic, mirroring the
+/// bdk key-derivation example. The mnemonic is parsed (or freshly generated),
+/// an xprv is derived for the BIP84/BIP86 account, external and internal
+/// descriptors are built and imported via `importdescriptors`. The mnemonic
+/// and derived descriptors are printed so the wallet can be backed up and
+/// restored reproducibly.
+fn create_descriptor_wallet(
+    config: &Config,
+    wallet: &str,
+    mnemonic: Option<&str>,
+    taproot: bool,
+) -> bitcoincore_rpc::Result<Client> {
+    let rpc = config.client()?;
+
+    let mnemonic = match mnemonic {
+        Some(words) => Mnemonic::parse(words).expect("Invalid BIP39 mnemonic"),
+        None => Mnemonic::generate(12).expect("Failed to generate mnemonic"),
+    };
+    println!("Mnemonic: {mnemonic}");
+
+    let seed = mnemonic.to_seed("");
+    let secp = Secp256k1::new();
+    let root = Xpriv::new_master(Network::Regtest, &seed).expect("Failed to derive master key");
+    let fingerprint = root.fingerprint(&secp);
+
+    // BIP84 (segwit) or BIP86 (taproot) account path on the regtest coin type.
+    let (purpose, kind) = if taproot { (86u32, "tr") } else { (84u32, "wpkh") };
+    let account_path: DerivationPath = format!("m/{purpose}'/1'/0'")
+        .parse()
+        .expect("Failed to build account derivation path");
+    let account_xprv = root
+        .derive_priv(&secp, &account_path)
+        .expect("Failed to derive account xprv");
+
+    // External (receive, `.../0/*`) and internal (change, `.../1/*`) branches.
+    // `getdescriptorinfo` returns the descriptor stripped of private keys, so we
+    // keep the xprv-bearing descriptor and only borrow its checksum. Importing
+    // the private descriptor yields a signing (not watch-only) wallet, and the
+    // printed backup retains the xprv needed to restore spending ability.
+    let origin = format!("[{fingerprint}/{purpose}'/1'/0']");
+    let priv_ext = format!("{kind}({origin}{account_xprv}/0/*)");
+    let priv_int = format!("{kind}({origin}{account_xprv}/1/*)");
+    let external = format!("{priv_ext}#{}", rpc.get_descriptor_info(&priv_ext)?.checksum);
+    let internal = format!("{priv_int}#{}", rpc.get_descriptor_info(&priv_int)?.checksum);
+    println!("External descriptor: {external}");
+    println!("Internal descriptor: {internal}");
+
+    // Create an empty descriptor wallet, then import the derived descriptors.
+    rpc.call::<serde_json::Value>(
+        "createwallet",
+        &[
+            json!(wallet),
+            json!(false),
+            json!(true),
+            json!(""),
+            json!(false),
+            json!(true),
+        ],
     )?;
 
-    // Get blockchain info
-    let blockchain_info = rpc.get_blockchain_info()?;
-    println!("Blockchain Info: {blockchain_info:#?}");
+    let wallet_rpc = config.wallet_client(wallet)?;
+    let requests = json!([
+        {"desc": external, "active": true, "internal": false, "timestamp": "now", "range": [0, 999]},
+        {"desc": internal, "active": true, "internal": true, "timestamp": "now", "range": [0, 999]},
+    ]);
+    wallet_rpc.call::<serde_json::Value>("importdescriptors", &[requests])?;
 
-    let miner_rpc = verify_wallet(&rpc, MINER_WALLET_NAME)?;
+    println!("Descriptor wallet '{wallet}' created and loaded.");
+    Ok(wallet_rpc)
+}
 
-    let trader_rpc = verify_wallet(&rpc, TRADER_WALLET_NAME)?;
+fn new_descriptor_wallet(
+    config: &Config,
+    wallet: &str,
+    mnemonic: Option<&str>,
+    taproot: bool,
+) -> bitcoincore_rpc::Result<()> {
+    let wallet_rpc = create_descriptor_wallet(config, wallet, mnemonic, taproot)?;
+    let address = wallet_rpc.get_new_address(None, None)?.assume_checked();
+    println!("Wallet '{wallet}' ready. New address: {address}");
+    Ok(())
+}
 
-    let miner_address = miner_rpc
-        .get_new_address(Some(MINER_ADDRESS_LABEL), None)?
+fn new_wallet(
+    config: &Config,
+    wallet: &str,
+    address_type: Option<AddressKind>,
+) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+    let address = wallet_rpc
+        .get_new_address(None, address_type.map(AddressKind::to_rpc))?
         .assume_checked();
-    println!("Miner Address: {miner_address}");
+    println!("Wallet '{wallet}' ready. New address: {address}");
+    Ok(())
+}
 
+fn mine(
+    config: &Config,
+    wallet: &str,
+    blocks: u64,
+    address_type: Option<AddressKind>,
+) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+    let address = wallet_rpc
+        .get_new_address(Some(MINER_ADDRESS_LABEL), address_type.map(AddressKind::to_rpc))?
+        .assume_checked();
+    println!("Mining {blocks} blocks to {address}...");
+    let hashes = rpc.generate_to_address(blocks, &address)?;
     println!(
-        "Mining {} blocks to mature coinbase transaction (100 blocks maturity + 1 block for the initial reward)...",
-        COINBASE_MATURITY + 1
+        "Mined {} blocks. Chain height is now {}.",
+        hashes.len(),
+        rpc.get_block_count()?
     );
+    Ok(())
+}
 
-    rpc.generate_to_address(COINBASE_MATURITY + 1, &miner_address)?;
+fn send_to_address(
+    config: &Config,
+    wallet: &str,
+    address: &str,
+    amount: f64,
+    fee_rate: Option<f64>,
+    replaceable: bool,
+) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+
+    let destination = address
+        .parse::<Address<_>>()
+        .expect("Invalid destination address")
+        .require_network(Network::Regtest)
+        .expect("Address is not valid for regtest");
+
+    // Build the positional `sendtoaddress` arguments so we can pass an explicit
+    // sat/vB fee rate, which the typed helper does not expose.
+    let mut params = vec![
+        json!(destination.to_string()),
+        json!(amount),
+        json!(""),
+        json!(""),
+        json!(false),
+        json!(replaceable),
+    ];
+    if let Some(rate) = fee_rate {
+        params.push(json!(null)); // conf_target
+        params.push(json!("unset")); // estimate_mode
+        params.push(json!(false)); // avoid_reuse
+        params.push(json!(rate)); // fee_rate (sat/vB)
+    }
+    let txid: bitcoin::Txid = wallet_rpc.call("sendtoaddress", &params)?;
+    println!("Sent {amount:.8} BTC from '{wallet}' to {destination}. Transaction ID: {txid}");
 
-    let miner_balance = miner_rpc.get_balance(None, None)?;
+    let mempool_entry = rpc.get_mempool_entry(&txid)?;
     println!(
-        "Miner wallet spendable balance: {:.8} BTC (after {} total blocks)",
-        miner_balance.to_btc(),
-        rpc.get_block_count()?
+        "Mempool entry for {txid}: fee = {:.8} BTC, vsize = {} vB",
+        mempool_entry.fees.base.to_btc(),
+        mempool_entry.vsize
     );
+    Ok(())
+}
 
-    let trader_address = trader_rpc
-        .get_new_address(Some(TRADER_ADDRESS_LABEL), None)?
-        .assume_checked();
-    println!("Trader Address: {trader_address}");
-
-    let amount_to_send = Amount::from_btc(TRANSACTION_AMOUNT_TO_SEND)?;
-
-    let transaction_id = miner_rpc.send_to_address(
-        &trader_address,
-        amount_to_send,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-    )?;
+fn bump_fee(
+    config: &Config,
+    wallet: &str,
+    txid: &str,
+    fee_rate: Option<f64>,
+) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+
+    let transaction_id: bitcoin::Txid = txid.parse().expect("Invalid transaction id");
+
+    let mut params = vec![json!(transaction_id.to_string())];
+    if let Some(rate) = fee_rate {
+        params.push(json!({ "fee_rate": rate }));
+    }
+    let result: serde_json::Value = wallet_rpc.call("bumpfee", &params)?;
+    println!("Fee-bump result for {transaction_id}: {result:#?}");
+
+    if let Some(new_txid) = result.get("txid").and_then(|v| v.as_str()) {
+        let mempool_entry = rpc.get_mempool_entry(&new_txid.parse().expect("Invalid bumped txid"))?;
+        println!(
+            "Replacement transaction {new_txid}: fee = {:.8} BTC, vsize = {} vB",
+            mempool_entry.fees.base.to_btc(),
+            mempool_entry.vsize
+        );
+    }
+    Ok(())
+}
+
+fn balance(config: &Config, wallet: &str) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+    let balance = wallet_rpc.get_balance(None, None)?;
     println!(
-        "Sent {TRANSACTION_AMOUNT_TO_SEND:.8} BTC from Miner to Trader. Transaction ID: {transaction_id}",
+        "Wallet '{wallet}' spendable balance: {:.8} BTC (chain height {})",
+        balance.to_btc(),
+        rpc.get_block_count()?
     );
+    Ok(())
+}
 
-    let mempool_entry = rpc.get_mempool_entry(&transaction_id)?;
-    println!("Mempool entry for transaction {transaction_id}: {mempool_entry:#?}",);
+/// Run libbitcoinconsensus over every input of `tx`, confirming that each
+/// input script actually spends its referenced previous output under
+/// consensus rules. Prints a pass line per input and aborts on the first
+/// script that fails to validate.
+fn verify_tx_consensus(rpc: &Client, tx: &bitcoin::Transaction) -> bitcoincore_rpc::Result<()> {
+    let spending_tx_bytes = bitcoin::consensus::encode::serialize(tx);
+
+    for (input_index, input) in tx.input.iter().enumerate() {
+        let prev_txid = input.previous_output.txid;
+        let prev_vout = input.previous_output.vout;
+        let prev_raw_tx = rpc.get_raw_transaction(&prev_txid, None)?;
+        let prev_output = &prev_raw_tx.output[prev_vout as usize];
+
+        if let Err(err) =
+            prev_output
+                .script_pubkey
+                .verify(input_index, prev_output.value, &spending_tx_bytes)
+        {
+            return Err(bitcoincore_rpc::Error::ReturnedError(format!(
+                "Consensus verification failed for input {input_index}: {err}"
+            )));
+        }
+        println!("  input {input_index}: script valid");
+    }
 
-    println!("Mining 1 block to confirm the transaction...");
+    Ok(())
+}
 
-    let confirmation_block_hashes = rpc.generate_to_address(1, &miner_address)?;
-    let confirmation_block_hash = confirmation_block_hashes[0];
-    println!("Transaction {transaction_id} confirmed in block: {confirmation_block_hash}",);
+/// Print the report as a pretty-printed JSON object.
+fn render_json(data: &TxReportData) -> bitcoincore_rpc::Result<()> {
+    let rendered = serde_json::to_string_pretty(data).expect("Failed to serialise report");
+    println!("{rendered}");
+    Ok(())
+}
 
-    let tx_details = miner_rpc.get_transaction(&transaction_id, None)?;
+/// Print the report as a human-readable two-column table.
+fn render_table(data: &TxReportData) -> bitcoincore_rpc::Result<()> {
+    let mut table = Table::new();
+    table.add_row(row!["Field", "Value"]);
+    table.add_row(row!["txid", data.txid]);
+    table.add_row(row!["miner_input_address", data.miner_input_address]);
+    table.add_row(row!["miner_input_amount", format!("{:.8}", data.miner_input_amount)]);
+    table.add_row(row!["trader_output_address", data.trader_output_address]);
+    table.add_row(row!["trader_output", format!("{:.8}", data.trader_output)]);
+    table.add_row(row!["miner_change_address", data.miner_change_address]);
+    table.add_row(row!["miner_change", format!("{:.8}", data.miner_change)]);
+    table.add_row(row!["fee", format!("{:.8}", data.fee)]);
+    table.add_row(row!["block_height", data.block_height]);
+    table.add_row(row!["block_hash", data.block_hash]);
+    table.printstd();
+    Ok(())
+}
 
-    let raw_tx = miner_rpc.get_raw_transaction(&transaction_id, None)?;
+/// Write the legacy ten-line `../out.txt` dump in its original field order.
+fn render_legacy(data: &TxReportData) -> bitcoincore_rpc::Result<()> {
+    let out_path = Path::new("../out.txt");
+    let mut output_file = File::create(out_path)?;
+
+    writeln!(output_file, "{}", data.txid)?;
+    writeln!(output_file, "{}", data.miner_input_address)?;
+    writeln!(output_file, "{:.8}", data.miner_input_amount)?;
+    writeln!(output_file, "{}", data.trader_output_address)?;
+    writeln!(output_file, "{:.8}", data.trader_output)?;
+    writeln!(output_file, "{}", data.miner_change_address)?;
+    writeln!(output_file, "{:.8}", data.miner_change)?;
+    writeln!(output_file, "{:.8}", data.fee)?;
+    writeln!(output_file, "{}", data.block_height)?;
+    writeln!(output_file, "{}", data.block_hash)?;
+
+    println!("\nTransaction details written to ../out.txt successfully!");
+    Ok(())
+}
+
+fn tx_report(
+    config: &Config,
+    wallet: &str,
+    txid: &str,
+    format: OutputFormat,
+) -> bitcoincore_rpc::Result<()> {
+    let rpc = config.client()?;
+    let wallet_rpc = verify_wallet(config, &rpc, wallet)?;
+
+    let transaction_id = txid.parse().expect("Invalid transaction id");
+
+    let tx_details = wallet_rpc.get_transaction(&transaction_id, None)?;
+    let raw_tx = wallet_rpc.get_raw_transaction(&transaction_id, None)?;
 
     let block_height = tx_details
         .info
@@ -125,18 +540,19 @@ fn main() -> bitcoincore_rpc::Result<()> {
         .blockhash
         .expect("Confirmed transaction must have a block hash.");
 
+    println!("Verifying transaction scripts against consensus rules...");
+    verify_tx_consensus(&rpc, &raw_tx)?;
+
     let first_input = &raw_tx.input[0];
     let prev_txid = first_input.previous_output.txid;
     let prev_vout_index = first_input.previous_output.vout;
 
     let prev_raw_tx = rpc.get_raw_transaction(&prev_txid, None)?;
-
     let prev_output = &prev_raw_tx.output[prev_vout_index as usize];
 
     let miner_input_address = Address::from_script(&prev_output.script_pubkey, Network::Regtest)
         .expect("Failed to decode miner's input address from script")
         .to_string();
-
     let miner_input_amount = prev_output.value.to_btc();
 
     let mut trader_output_address: String = String::new();
@@ -144,38 +560,98 @@ fn main() -> bitcoincore_rpc::Result<()> {
     let mut miner_change_address: String = String::new();
     let mut miner_change_amount: f64 = 0.0;
 
+    // Outputs the spending wallet owns are change; the rest are payments. This
+    // classification only holds when `wallet` is the spending wallet (see the
+    // `tx-report` command docs).
     for output in &raw_tx.output {
         let output_address = Address::from_script(&output.script_pubkey, Network::Regtest)
             .expect("Failed to decode output address from script");
         let output_amount_btc = output.value.to_btc();
 
-        if output_address == trader_address {
-            trader_output_address = output_address.to_string();
-            trader_output_amount = output_amount_btc;
-        } else {
+        let is_change = wallet_rpc
+            .get_address_info(&output_address)
+            .map(|info| info.is_mine.unwrap_or(false))
+            .unwrap_or(false);
+
+        if is_change {
             miner_change_address = output_address.to_string();
             miner_change_amount = output_amount_btc;
+        } else {
+            trader_output_address = output_address.to_string();
+            trader_output_amount = output_amount_btc;
         }
     }
 
-    let transaction_fees = tx_details.fee.unwrap().to_btc().abs();
+    let fee = tx_details.fee.unwrap().to_btc().abs();
+
+    let data = TxReportData {
+        txid: transaction_id.to_string(),
+        miner_input_address,
+        miner_input_amount,
+        trader_output: trader_output_amount,
+        trader_output_address,
+        miner_change: miner_change_amount,
+        miner_change_address,
+        fee,
+        block_height,
+        block_hash: block_hash.to_string(),
+    };
+
+    match format {
+        OutputFormat::Json => render_json(&data)?,
+        OutputFormat::Table => render_table(&data)?,
+        OutputFormat::Legacy => render_legacy(&data)?,
+    }
 
-    let out_path = Path::new("../out.txt");
-    let mut output_file = File::create(out_path)?;
+    Ok(())
+}
 
-    writeln!(output_file, "{transaction_id}")?;
-    writeln!(output_file, "{miner_input_address}")?;
-    writeln!(output_file, "{miner_input_amount:.8}")?;
-    writeln!(output_file, "{trader_output_address}")?;
-    writeln!(output_file, "{trader_output_amount:.8}")?;
-    writeln!(output_file, "{miner_change_address}")?;
-    writeln!(output_file, "{miner_change_amount:.8}")?;
-    writeln!(output_file, "{transaction_fees:.8}")?;
-    writeln!(output_file, "{block_height}")?;
-    writeln!(output_file, "{block_hash}")?;
+fn main() -> bitcoincore_rpc::Result<()> {
+    let cli = Cli::parse();
+    let config = Config::from_env();
+
+    // Only dump the full chain state when explicitly asked; per-command tooling
+    // should stay quiet by default.
+    if cli.verbose {
+        let rpc = config.client()?;
+        let blockchain_info = rpc.get_blockchain_info()?;
+        println!("Blockchain Info: {blockchain_info:#?}");
+    }
 
-    println!("\nTransaction details written to ../out.txt successfully!");
-    println!("Program completed successfully!");
+    match &cli.command {
+        Command::NewWallet {
+            wallet,
+            address_type,
+        } => new_wallet(&config, wallet, *address_type)?,
+        Command::NewDescriptorWallet {
+            wallet,
+            mnemonic,
+            taproot,
+        } => new_descriptor_wallet(&config, wallet, mnemonic.as_deref(), *taproot)?,
+        Command::Mine {
+            wallet,
+            blocks,
+            address_type,
+        } => mine(&config, wallet, *blocks, *address_type)?,
+        Command::SendToAddress {
+            wallet,
+            address,
+            amount,
+            fee_rate,
+            replaceable,
+        } => send_to_address(&config, wallet, address, *amount, *fee_rate, *replaceable)?,
+        Command::BumpFee {
+            wallet,
+            txid,
+            fee_rate,
+        } => bump_fee(&config, wallet, txid, *fee_rate)?,
+        Command::Balance { wallet } => balance(&config, wallet)?,
+        Command::TxReport {
+            wallet,
+            txid,
+            format,
+        } => tx_report(&config, wallet, txid, *format)?,
+    }
 
     Ok(())
 }